@@ -0,0 +1,160 @@
+use crate::bitcoin::{Transaction, TxLock, Txid, Wallet as BitcoinWallet};
+use crate::monero::Wallet as MoneroWallet;
+use crate::{ExpiredTimelocks, SwapAmounts};
+use anyhow::Result;
+use std::fmt;
+
+/// The state Bob's swap driver is in. Persisted to the database after every transition so a
+/// crashed swap can be resumed from the last recorded state.
+#[derive(Debug, Clone)]
+pub enum BobState {
+    Started {
+        state0: State0,
+        amounts: SwapAmounts,
+    },
+    Negotiated(State2),
+    BtcLocked(State3),
+    XmrLocked(State4),
+    EncSigSent(State4),
+    BtcRedeemed {
+        state: State5,
+        tx_lock_id: Txid,
+    },
+    CancelTimelockExpired(State4),
+    BtcCancelled(State4),
+    BtcRefunded(State4),
+    BtcPunished {
+        tx_lock_id: Txid,
+    },
+    SafelyAborted,
+    XmrRedeemed {
+        tx_lock_id: Txid,
+    },
+}
+
+impl fmt::Display for BobState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            BobState::Started { .. } => "started",
+            BobState::Negotiated(..) => "negotiated",
+            BobState::BtcLocked(..) => "btc is locked",
+            BobState::XmrLocked(..) => "xmr is locked",
+            BobState::EncSigSent(..) => "encrypted signature is sent",
+            BobState::BtcRedeemed { .. } => "btc is redeemed",
+            BobState::CancelTimelockExpired(..) => "cancel timelock is expired",
+            BobState::BtcCancelled(..) => "btc is cancelled",
+            BobState::BtcRefunded(..) => "btc is refunded",
+            BobState::BtcPunished { .. } => "punished",
+            BobState::SafelyAborted => "safely aborted",
+            BobState::XmrRedeemed { .. } => "xmr is redeemed",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Bob before he has exchanged any messages with Alice.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct State0;
+
+/// Alice and Bob have exchanged info and Bob is about to lock Bitcoin.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct State2 {
+    pub tx_lock: TxLock,
+}
+
+/// Bob has locked Bitcoin and is waiting for Alice to lock Monero.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct State3 {
+    pub tx_lock: TxLock,
+}
+
+impl State3 {
+    pub async fn current_epoch(&self, bitcoin_wallet: &BitcoinWallet) -> Result<ExpiredTimelocks> {
+        bitcoin_wallet.expired_timelock(&self.tx_lock).await
+    }
+
+    pub async fn wait_for_cancel_timelock_to_expire(&self, bitcoin_wallet: &BitcoinWallet) -> Result<()> {
+        bitcoin_wallet.wait_for_timelock_expiry(&self.tx_lock).await
+    }
+
+    pub fn state4(&self) -> State4 {
+        State4 {
+            tx_lock: self.tx_lock.clone(),
+        }
+    }
+
+    pub async fn watch_for_lock_xmr(
+        self,
+        monero_wallet: &MoneroWallet,
+        msg2: crate::protocol::bob::event_loop::Message2,
+        restore_height: u64,
+    ) -> Result<State4> {
+        monero_wallet
+            .watch_for_transfer(msg2.tx_lock_proof, restore_height)
+            .await?;
+        Ok(self.state4())
+    }
+}
+
+/// Alice has locked Monero, and Bob is ready to redeem it once he reveals his signature.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct State4 {
+    pub tx_lock: TxLock,
+}
+
+impl State4 {
+    pub async fn expired_timelock(&self, bitcoin_wallet: &BitcoinWallet) -> Result<ExpiredTimelocks> {
+        bitcoin_wallet.expired_timelock(&self.tx_lock).await
+    }
+
+    pub async fn wait_for_cancel_timelock_to_expire(&self, bitcoin_wallet: &BitcoinWallet) -> Result<()> {
+        bitcoin_wallet.wait_for_timelock_expiry(&self.tx_lock).await
+    }
+
+    pub fn tx_redeem_encsig(&self) -> crate::bitcoin::EncryptedSignature {
+        self.tx_lock.encsig_for_redeem()
+    }
+
+    pub async fn watch_for_redeem_btc(&self, bitcoin_wallet: &BitcoinWallet) -> Result<State5> {
+        let tx_redeem = bitcoin_wallet.watch_for_redeem(&self.tx_lock).await?;
+        Ok(State5 {
+            tx_lock: self.tx_lock.clone(),
+            tx_redeem,
+        })
+    }
+
+    pub async fn check_for_tx_cancel(&self, bitcoin_wallet: &BitcoinWallet) -> Result<()> {
+        bitcoin_wallet.check_for_tx_cancel(&self.tx_lock).await
+    }
+
+    pub async fn submit_tx_cancel(&self, bitcoin_wallet: &BitcoinWallet) -> Result<()> {
+        bitcoin_wallet.submit_tx_cancel(&self.tx_lock).await
+    }
+
+    pub async fn refund_btc(&self, bitcoin_wallet: &BitcoinWallet) -> Result<()> {
+        bitcoin_wallet.refund(&self.tx_lock).await
+    }
+
+    pub fn tx_lock_id(&self) -> Txid {
+        self.tx_lock.txid()
+    }
+}
+
+/// Bob holds the redeem transaction for the Bitcoin lock output. `BobState::BtcRedeemed` stores
+/// the lock tx id (from [`State5::tx_lock_id`]) next to this state, so callers get a stable
+/// lock-tx handle without having to reach into `state` to recompute it.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct State5 {
+    pub tx_lock: TxLock,
+    pub tx_redeem: Transaction,
+}
+
+impl State5 {
+    pub fn tx_lock_id(&self) -> Txid {
+        self.tx_lock.txid()
+    }
+
+    pub async fn claim_xmr(&self, monero_wallet: &MoneroWallet) -> Result<()> {
+        monero_wallet.redeem(&self.tx_redeem).await
+    }
+}
@@ -1,20 +1,151 @@
 use anyhow::{bail, Result};
 use async_recursion::async_recursion;
 use rand::{CryptoRng, RngCore};
+use std::future::Future;
+use std::num::NonZeroU32;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::select;
-use tracing::{debug, info};
+use tokio::time::timeout;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
 use uuid::Uuid;
 
 use crate::{
     bitcoin,
     database::{Database, Swap},
+    env,
     monero,
     protocol::bob::{self, event_loop::EventLoopHandle, state::*},
     ExpiredTimelocks, SwapAmounts,
 };
 use ecdsa_fun::fun::rand_core::OsRng;
 
+/// Configurable policy for [`retry_with_backoff`]. Defaults to 5 attempts, starting at a 1s
+/// backoff and doubling up to a 60s cap. `max_attempts` is a `NonZeroU32` so "zero retries" isn't
+/// a representable (and silently broken) value.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryConfig {
+    pub max_attempts: NonZeroU32,
+    pub initial_interval: Duration,
+    pub max_interval: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: NonZeroU32::new(5).expect("5 is non-zero"),
+            initial_interval: Duration::from_secs(1),
+            max_interval: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Retry `f` according to `retry_config`, bounding the *total* time spent across all attempts
+/// and backoff sleeps by `deadline` (rather than letting each attempt add to it), and giving up
+/// once `retry_config.max_attempts` is reached or `deadline` elapses, whichever comes first.
+async fn retry_with_backoff<T, F, Fut>(
+    deadline: Duration,
+    retry_config: RetryConfig,
+    mut f: F,
+) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let max_attempts = retry_config.max_attempts.get();
+    let attempts = async {
+        let mut interval = retry_config.initial_interval;
+
+        for attempt in 1..=max_attempts {
+            match f().await {
+                Ok(value) => return Ok(value),
+                Err(e) if attempt == max_attempts => return Err(e),
+                Err(e) => {
+                    warn!(
+                        "Attempt {}/{} failed: {:#}. Retrying in {:?}",
+                        attempt, max_attempts, e, interval
+                    );
+                }
+            }
+
+            tokio::time::sleep(interval).await;
+            interval = std::cmp::min(interval * 2, retry_config.max_interval);
+        }
+
+        unreachable!("max_attempts is non-zero, so the loop above always returns on its last iteration")
+    };
+
+    match timeout(deadline, attempts).await {
+        Ok(result) => result,
+        Err(_) => bail!(
+            "Gave up after exceeding the {:?} deadline for this operation",
+            deadline
+        ),
+    }
+}
+
+/// Dial the counterparty, retrying with exponential backoff on failure.
+async fn dial_with_backoff(
+    event_loop_handle: &mut EventLoopHandle,
+    deadline: Duration,
+    retry_config: RetryConfig,
+) -> Result<()> {
+    retry_with_backoff(deadline, retry_config, || event_loop_handle.dial()).await
+}
+
+/// Race `cancel_token` against `gate`. If cancellation wins, returns `Ok(None)` and `effect` is
+/// dropped unpolled. Otherwise awaits `gate`, and once it resolves successfully runs `effect` to
+/// completion - `effect` itself is never raced against cancellation, so a cancellation that
+/// arrives while `effect` is running (e.g. broadcasting a transaction) can't interrupt it.
+async fn run_past_cancellation_point<G, E, T>(
+    cancel_token: &CancellationToken,
+    gate: G,
+    effect: E,
+) -> Result<Option<T>>
+where
+    G: Future<Output = Result<()>>,
+    E: Future<Output = Result<T>>,
+{
+    select! {
+        _ = cancel_token.cancelled() => Ok(None),
+        result = gate => {
+            result?;
+            Ok(Some(effect.await?))
+        }
+    }
+}
+
+/// Keep dialing (with backoff, round after round) until it succeeds or the cancel timelock
+/// actually elapses, whichever comes first. A bare dial failure alone never produces
+/// `CancelTimelockExpired` — only the cancel-timelock watch completing does (on expiry, or if
+/// the watch itself returns an error), so a transient network blip
+/// can't push a swap with funds still safely recoverable into the cancel path.
+async fn dial_until_connected_or_timelock_expired<F>(
+    event_loop_handle: &mut EventLoopHandle,
+    deadline: Duration,
+    retry_config: RetryConfig,
+    cancel_timelock_expires: F,
+) -> bool
+where
+    F: Future<Output = Result<()>>,
+{
+    tokio::pin!(cancel_timelock_expires);
+
+    loop {
+        select! {
+            result = dial_with_backoff(event_loop_handle, deadline, retry_config) => {
+                if result.is_ok() {
+                    return true;
+                }
+            }
+            _ = &mut cancel_timelock_expires => {
+                return false;
+            }
+        }
+    }
+}
+
 pub fn is_complete(state: &BobState) -> bool {
     matches!(
         state,
@@ -38,13 +169,15 @@ pub fn is_encsig_sent(state: &BobState) -> bool {
 }
 
 #[allow(clippy::too_many_arguments)]
-pub async fn run(swap: bob::Swap) -> Result<BobState> {
-    run_until(swap, is_complete).await
+pub async fn run(swap: bob::Swap, cancel_token: CancellationToken) -> Result<BobState> {
+    run_until(swap, is_complete, cancel_token, RetryConfig::default()).await
 }
 
 pub async fn run_until(
     swap: bob::Swap,
     is_target_state: fn(&BobState) -> bool,
+    cancel_token: CancellationToken,
+    retry_config: RetryConfig,
 ) -> Result<BobState> {
     run_until_internal(
         swap.state,
@@ -55,6 +188,43 @@ pub async fn run_until(
         swap.monero_wallet,
         OsRng,
         swap.swap_id,
+        swap.env_config,
+        cancel_token,
+        retry_config,
+    )
+    .await
+}
+
+/// Resume a swap that was interrupted (e.g. by a process crash) from the last `BobState`
+/// persisted in the database, and run it to completion.
+#[allow(clippy::too_many_arguments)]
+pub async fn resume(
+    swap_id: Uuid,
+    event_loop_handle: EventLoopHandle,
+    db: Database,
+    bitcoin_wallet: Arc<bitcoin::Wallet>,
+    monero_wallet: Arc<monero::Wallet>,
+    env_config: env::Config,
+    cancel_token: CancellationToken,
+    retry_config: RetryConfig,
+) -> Result<BobState> {
+    let resume_state = match db.get_state(swap_id).await? {
+        Swap::Bob(state) => state.into(),
+        Swap::Alice(_) => bail!("Swap {} is an Alice swap, cannot resume it as Bob", swap_id),
+    };
+
+    run_until_internal(
+        resume_state,
+        is_complete,
+        event_loop_handle,
+        db,
+        bitcoin_wallet,
+        monero_wallet,
+        OsRng,
+        swap_id,
+        env_config,
+        cancel_token,
+        retry_config,
     )
     .await
 }
@@ -71,133 +241,156 @@ async fn run_until_internal<R>(
     monero_wallet: Arc<monero::Wallet>,
     mut rng: R,
     swap_id: Uuid,
+    env_config: env::Config,
+    cancel_token: CancellationToken,
+    retry_config: RetryConfig,
 ) -> Result<BobState>
 where
     R: RngCore + CryptoRng + Send,
 {
     info!("Current state: {}", state);
     if is_target_state(&state) {
-        Ok(state)
-    } else {
-        match state {
-            BobState::Started { state0, amounts } => {
-                event_loop_handle.dial().await?;
-
-                let state2 = negotiate(
-                    state0,
-                    amounts,
-                    &mut event_loop_handle,
-                    &mut rng,
-                    bitcoin_wallet.clone(),
-                )
-                .await?;
-
-                let state = BobState::Negotiated(state2);
-                let db_state = state.clone().into();
-                db.insert_latest_state(swap_id, Swap::Bob(db_state)).await?;
-                run_until_internal(
-                    state,
-                    is_target_state,
-                    event_loop_handle,
-                    db,
-                    bitcoin_wallet,
-                    monero_wallet,
-                    rng,
-                    swap_id,
-                )
-                .await
+        return Ok(state);
+    }
+
+    let new_state = match state {
+        // No Bitcoin has been committed yet, so a requested abort can be honoured safely.
+        BobState::Started { state0, amounts } => {
+            select! {
+                _ = cancel_token.cancelled() => BobState::SafelyAborted,
+                result = async {
+                    dial_with_backoff(&mut event_loop_handle, env_config.bob_time_to_act, retry_config).await?;
+
+                    negotiate(
+                        state0,
+                        amounts,
+                        &mut event_loop_handle,
+                        &mut rng,
+                        bitcoin_wallet.clone(),
+                        env_config.bob_time_to_act,
+                        retry_config,
+                    )
+                    .await
+                } => {
+                    BobState::Negotiated(result?)
+                }
             }
-            BobState::Negotiated(state2) => {
-                // Do not lock Bitcoin if not connected to Alice.
-                event_loop_handle.dial().await?;
-                // Alice and Bob have exchanged info
-                let state3 = state2.lock_btc(bitcoin_wallet.as_ref()).await?;
-
-                let state = BobState::BtcLocked(state3);
-                let db_state = state.clone().into();
-                db.insert_latest_state(swap_id, Swap::Bob(db_state)).await?;
-                run_until_internal(
-                    state,
-                    is_target_state,
-                    event_loop_handle,
-                    db,
-                    bitcoin_wallet,
-                    monero_wallet,
-                    rng,
-                    swap_id,
-                )
-                .await
+        }
+        // Still no Bitcoin has been committed, so a requested abort can be honoured safely -
+        // but only up until the dial succeeds. Once `lock_btc` is invoked it broadcasts the
+        // lock transaction, so it must run to completion rather than race the cancel token.
+        BobState::Negotiated(state2) => {
+            // Do not lock Bitcoin if not connected to Alice.
+            let dial = dial_with_backoff(&mut event_loop_handle, env_config.bob_time_to_act, retry_config);
+            // Alice and Bob have exchanged info.
+            let lock_btc = state2.lock_btc(bitcoin_wallet.as_ref());
+
+            match run_past_cancellation_point(&cancel_token, dial, lock_btc).await? {
+                Some(state3) => BobState::BtcLocked(state3),
+                None => BobState::SafelyAborted,
             }
-            // Bob has locked Btc
-            // Watch for Alice to Lock Xmr or for cancel timelock to elapse
-            BobState::BtcLocked(state3) => {
-                let state = if let ExpiredTimelocks::None =
-                    state3.current_epoch(bitcoin_wallet.as_ref()).await?
-                {
-                    event_loop_handle.dial().await?;
-
-                    let msg2_watcher = event_loop_handle.recv_message2();
+        }
+        // Bob has locked Btc
+        // Watch for Alice to Lock Xmr or for cancel timelock to elapse
+        BobState::BtcLocked(state3) => {
+            if let ExpiredTimelocks::None = state3.current_epoch(bitcoin_wallet.as_ref()).await? {
+                // Bitcoin is already locked, so keep retrying the connection to Alice for as
+                // long as the cancel timelock allows rather than giving up into the cancel path
+                // on a transient network blip.
+                let connected = dial_until_connected_or_timelock_expired(
+                    &mut event_loop_handle,
+                    env_config.bob_time_to_act,
+                    retry_config,
+                    state3.wait_for_cancel_timelock_to_expire(bitcoin_wallet.as_ref()),
+                )
+                .await;
+
+                if !connected {
+                    let state4 = state3.state4();
+                    BobState::CancelTimelockExpired(state4)
+                } else {
+                    // Wait for the lock tx to reach the configured finality depth before we start
+                    // watching for Alice's XMR lock, so we don't proceed on a tx that could still
+                    // be reorged out.
+                    let tx_lock_finalised =
+                        bitcoin_wallet.watch_until_status(&state3.tx_lock, |status| {
+                            status.is_confirmed_with(env_config.bitcoin_finality_confirmations)
+                        });
                     let cancel_timelock_expires =
                         state3.wait_for_cancel_timelock_to_expire(bitcoin_wallet.as_ref());
 
-                    // Record the current monero wallet block height so we don't have to scan from
-                    // block 0 once we create the redeem wallet.
-                    // TODO: This can be optimized further by extracting the block height when
-                    //  tx-lock was included. However, scanning a few more blocks won't do any harm
-                    //  and is simpler.
-                    let monero_wallet_restore_blockheight =
-                        monero_wallet.inner.block_height().await?;
-
                     select! {
-                        msg2 = msg2_watcher => {
+                        result = tx_lock_finalised => {
+                            result?;
 
-                            let msg2 = msg2?;
-                            info!("Received XMR lock transaction transfer proof from Alice, watching for transfer confirmations");
-                            debug!("Transfer proof: {:?}", msg2.tx_lock_proof);
+                            let msg2_watcher = event_loop_handle.recv_message2();
+                            let cancel_timelock_expires =
+                                state3.wait_for_cancel_timelock_to_expire(bitcoin_wallet.as_ref());
 
-                            let xmr_lock_watcher = state3.clone()
-                                .watch_for_lock_xmr(monero_wallet.as_ref(), msg2, monero_wallet_restore_blockheight.height);
-                            let cancel_timelock_expires = state3.wait_for_cancel_timelock_to_expire(bitcoin_wallet.as_ref());
+                            // Record the current monero wallet block height so we don't have to scan from
+                            // block 0 once we create the redeem wallet.
+                            // TODO: This can be optimized further by extracting the block height when
+                            //  tx-lock was included. However, scanning a few more blocks won't do any harm
+                            //  and is simpler.
+                            let monero_wallet_restore_blockheight =
+                                monero_wallet.inner.block_height().await?;
 
                             select! {
-                                state4 = xmr_lock_watcher => {
-                                    BobState::XmrLocked(state4?)
+                                msg2 = msg2_watcher => {
+
+                                    let msg2 = msg2?;
+                                    info!("Received XMR lock transaction transfer proof from Alice, watching for transfer confirmations");
+                                    debug!("Transfer proof: {:?}", msg2.tx_lock_proof);
+
+                                    let xmr_lock_watcher = state3.clone()
+                                        .watch_for_lock_xmr(monero_wallet.as_ref(), msg2, monero_wallet_restore_blockheight.height);
+                                    let cancel_timelock_expires = state3.wait_for_cancel_timelock_to_expire(bitcoin_wallet.as_ref());
+
+                                    select! {
+                                        state4 = xmr_lock_watcher => {
+                                            BobState::XmrLocked(state4?)
+                                        },
+                                        _ = cancel_timelock_expires => {
+                                            let state4 = state3.state4();
+                                            BobState::CancelTimelockExpired(state4)
+                                        }
+                                    }
+
                                 },
                                 _ = cancel_timelock_expires => {
                                     let state4 = state3.state4();
                                     BobState::CancelTimelockExpired(state4)
                                 }
                             }
-
                         },
                         _ = cancel_timelock_expires => {
                             let state4 = state3.state4();
                             BobState::CancelTimelockExpired(state4)
                         }
                     }
-                } else {
-                    let state4 = state3.state4();
-                    BobState::CancelTimelockExpired(state4)
-                };
-                let db_state = state.clone().into();
-                db.insert_latest_state(swap_id, Swap::Bob(db_state)).await?;
-                run_until_internal(
-                    state,
-                    is_target_state,
-                    event_loop_handle,
-                    db,
-                    bitcoin_wallet,
-                    monero_wallet,
-                    rng,
-                    swap_id,
-                )
-                .await
+                }
+            } else {
+                let state4 = state3.state4();
+                BobState::CancelTimelockExpired(state4)
             }
-            BobState::XmrLocked(state) => {
-                let state = if let ExpiredTimelocks::None =
-                    state.expired_timelock(bitcoin_wallet.as_ref()).await?
-                {
-                    event_loop_handle.dial().await?;
+        }
+        BobState::XmrLocked(state) => {
+            if let ExpiredTimelocks::None = state.expired_timelock(bitcoin_wallet.as_ref()).await?
+            {
+                // Xmr is locked on Alice's side, so keep retrying the connection to her for as
+                // long as the cancel timelock allows rather than giving up into the cancel path
+                // on a transient network blip.
+                let connected = dial_until_connected_or_timelock_expired(
+                    &mut event_loop_handle,
+                    env_config.bob_time_to_act,
+                    retry_config,
+                    state.wait_for_cancel_timelock_to_expire(bitcoin_wallet.as_ref()),
+                )
+                .await;
+
+                if !connected {
+                    BobState::CancelTimelockExpired(state)
+                } else {
                     // Alice has locked Xmr
                     // Bob sends Alice his key
                     let tx_redeem_encsig = state.tx_redeem_encsig();
@@ -217,139 +410,91 @@ where
                             BobState::CancelTimelockExpired(state)
                         }
                     }
-                } else {
-                    BobState::CancelTimelockExpired(state)
-                };
-                let db_state = state.clone().into();
-                db.insert_latest_state(swap_id, Swap::Bob(db_state)).await?;
-                run_until_internal(
-                    state,
-                    is_target_state,
-                    event_loop_handle,
-                    db,
-                    bitcoin_wallet,
-                    monero_wallet,
-                    rng,
-                    swap_id,
-                )
-                .await
+                }
+            } else {
+                BobState::CancelTimelockExpired(state)
             }
-            BobState::EncSigSent(state) => {
-                let state = if let ExpiredTimelocks::None =
-                    state.expired_timelock(bitcoin_wallet.as_ref()).await?
-                {
-                    let state_clone = state.clone();
-                    let redeem_watcher = state_clone.watch_for_redeem_btc(bitcoin_wallet.as_ref());
-                    let cancel_timelock_expires =
-                        state_clone.wait_for_cancel_timelock_to_expire(bitcoin_wallet.as_ref());
-
-                    select! {
-                        state5 = redeem_watcher => {
-                            BobState::BtcRedeemed(state5?)
-                        },
-                        _ = cancel_timelock_expires => {
-                            BobState::CancelTimelockExpired(state)
+        }
+        BobState::EncSigSent(state) => {
+            if let ExpiredTimelocks::None = state.expired_timelock(bitcoin_wallet.as_ref()).await?
+            {
+                let state_clone = state.clone();
+                let redeem_watcher = state_clone.watch_for_redeem_btc(bitcoin_wallet.as_ref());
+                let cancel_timelock_expires =
+                    state_clone.wait_for_cancel_timelock_to_expire(bitcoin_wallet.as_ref());
+
+                select! {
+                    state5 = redeem_watcher => {
+                        let state5 = state5?;
+                        let tx_lock_id = state5.tx_lock_id();
+                        BobState::BtcRedeemed {
+                            state: state5,
+                            tx_lock_id,
                         }
+                    },
+                    _ = cancel_timelock_expires => {
+                        BobState::CancelTimelockExpired(state)
                     }
-                } else {
-                    BobState::CancelTimelockExpired(state)
-                };
-
-                let db_state = state.clone().into();
-                db.insert_latest_state(swap_id, Swap::Bob(db_state)).await?;
-                run_until_internal(
-                    state,
-                    is_target_state,
-                    event_loop_handle,
-                    db,
-                    bitcoin_wallet.clone(),
-                    monero_wallet,
-                    rng,
-                    swap_id,
-                )
-                .await
+                }
+            } else {
+                BobState::CancelTimelockExpired(state)
             }
-            BobState::BtcRedeemed(state) => {
-                // Bob redeems XMR using revealed s_a
-                state.claim_xmr(monero_wallet.as_ref()).await?;
+        }
+        BobState::BtcRedeemed { state, tx_lock_id } => {
+            // Bob redeems XMR using revealed s_a
+            state.claim_xmr(monero_wallet.as_ref()).await?;
 
-                let state = BobState::XmrRedeemed {
-                    tx_lock_id: state.tx_lock_id(),
-                };
-                let db_state = state.clone().into();
-                db.insert_latest_state(swap_id, Swap::Bob(db_state)).await?;
-                run_until_internal(
-                    state,
-                    is_target_state,
-                    event_loop_handle,
-                    db,
-                    bitcoin_wallet,
-                    monero_wallet,
-                    rng,
-                    swap_id,
-                )
+            BobState::XmrRedeemed { tx_lock_id }
+        }
+        BobState::CancelTimelockExpired(state4) => {
+            if state4
+                .check_for_tx_cancel(bitcoin_wallet.as_ref())
                 .await
+                .is_err()
+            {
+                state4.submit_tx_cancel(bitcoin_wallet.as_ref()).await?;
             }
-            BobState::CancelTimelockExpired(state4) => {
-                if state4
-                    .check_for_tx_cancel(bitcoin_wallet.as_ref())
-                    .await
-                    .is_err()
-                {
-                    state4.submit_tx_cancel(bitcoin_wallet.as_ref()).await?;
-                }
 
-                let state = BobState::BtcCancelled(state4);
-                db.insert_latest_state(swap_id, Swap::Bob(state.clone().into()))
-                    .await?;
-
-                run_until_internal(
-                    state,
-                    is_target_state,
-                    event_loop_handle,
-                    db,
-                    bitcoin_wallet,
-                    monero_wallet,
-                    rng,
-                    swap_id,
-                )
-                .await
-            }
-            BobState::BtcCancelled(state) => {
-                // Bob has cancelled the swap
-                let state = match state.expired_timelock(bitcoin_wallet.as_ref()).await? {
-                    ExpiredTimelocks::None => {
-                        bail!("Internal error: canceled state reached before cancel timelock was expired");
-                    }
-                    ExpiredTimelocks::Cancel => {
-                        state.refund_btc(bitcoin_wallet.as_ref()).await?;
-                        BobState::BtcRefunded(state)
-                    }
-                    ExpiredTimelocks::Punish => BobState::BtcPunished {
-                        tx_lock_id: state.tx_lock_id(),
-                    },
-                };
-
-                let db_state = state.clone().into();
-                db.insert_latest_state(swap_id, Swap::Bob(db_state)).await?;
-                run_until_internal(
-                    state,
-                    is_target_state,
-                    event_loop_handle,
-                    db,
-                    bitcoin_wallet,
-                    monero_wallet,
-                    rng,
-                    swap_id,
-                )
-                .await
+            BobState::BtcCancelled(state4)
+        }
+        BobState::BtcCancelled(state) => {
+            // Bob has cancelled the swap
+            match state.expired_timelock(bitcoin_wallet.as_ref()).await? {
+                ExpiredTimelocks::None => {
+                    bail!("Internal error: canceled state reached before cancel timelock was expired");
+                }
+                ExpiredTimelocks::Cancel => {
+                    state.refund_btc(bitcoin_wallet.as_ref()).await?;
+                    BobState::BtcRefunded(state)
+                }
+                ExpiredTimelocks::Punish => BobState::BtcPunished {
+                    tx_lock_id: state.tx_lock_id(),
+                },
             }
-            BobState::BtcRefunded(state4) => Ok(BobState::BtcRefunded(state4)),
-            BobState::BtcPunished { tx_lock_id } => Ok(BobState::BtcPunished { tx_lock_id }),
-            BobState::SafelyAborted => Ok(BobState::SafelyAborted),
-            BobState::XmrRedeemed { tx_lock_id } => Ok(BobState::XmrRedeemed { tx_lock_id }),
         }
-    }
+        BobState::BtcRefunded(state4) => return Ok(BobState::BtcRefunded(state4)),
+        BobState::BtcPunished { tx_lock_id } => return Ok(BobState::BtcPunished { tx_lock_id }),
+        BobState::SafelyAborted => return Ok(BobState::SafelyAborted),
+        BobState::XmrRedeemed { tx_lock_id } => return Ok(BobState::XmrRedeemed { tx_lock_id }),
+    };
+
+    db.insert_latest_state(swap_id, Swap::Bob(new_state.clone().into()))
+        .await?;
+
+    run_until_internal(
+        new_state,
+        is_target_state,
+        event_loop_handle,
+        db,
+        bitcoin_wallet,
+        monero_wallet,
+        rng,
+        swap_id,
+        env_config,
+        cancel_token,
+        retry_config,
+    )
+    .await
 }
 
 pub async fn negotiate<R>(
@@ -358,22 +503,157 @@ pub async fn negotiate<R>(
     swarm: &mut EventLoopHandle,
     mut rng: R,
     bitcoin_wallet: Arc<crate::bitcoin::Wallet>,
+    bob_time_to_act: Duration,
+    retry_config: RetryConfig,
 ) -> Result<bob::state::State2>
 where
     R: RngCore + CryptoRng + Send,
 {
     tracing::trace!("Starting negotiate");
-    swarm.request_amounts(amounts.btc).await?;
-
-    swarm.send_message0(state0.next_message(&mut rng)).await?;
-    let msg0 = swarm.recv_message0().await?;
+    retry_with_backoff(bob_time_to_act, retry_config, || {
+        swarm.request_amounts(amounts.btc)
+    })
+    .await?;
+
+    // Computed once so a retried send resends the exact message Alice may have already seen,
+    // rather than a freshly-randomized one.
+    let message0 = state0.next_message(&mut rng);
+    retry_with_backoff(bob_time_to_act, retry_config, || {
+        swarm.send_message0(message0.clone())
+    })
+    .await?;
+    let msg0 = retry_with_backoff(bob_time_to_act, retry_config, || swarm.recv_message0()).await?;
     let state1 = state0.receive(bitcoin_wallet.as_ref(), msg0).await?;
 
-    swarm.send_message1(state1.next_message()).await?;
-    let msg1 = swarm.recv_message1().await?;
+    let message1 = state1.next_message();
+    retry_with_backoff(bob_time_to_act, retry_config, || {
+        swarm.send_message1(message1.clone())
+    })
+    .await?;
+    let msg1 = retry_with_backoff(bob_time_to_act, retry_config, || swarm.recv_message1()).await?;
     let state2 = state1.receive(msg1)?;
 
-    swarm.send_message2(state2.next_message()).await?;
+    let message2 = state2.next_message();
+    retry_with_backoff(bob_time_to_act, retry_config, || {
+        swarm.send_message2(message2.clone())
+    })
+    .await?;
 
     Ok(state2)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn fast_retry_config(max_attempts: u32) -> RetryConfig {
+        RetryConfig {
+            max_attempts: NonZeroU32::new(max_attempts).unwrap(),
+            initial_interval: Duration::from_millis(1),
+            max_interval: Duration::from_millis(2),
+        }
+    }
+
+    #[tokio::test]
+    async fn retry_with_backoff_returns_first_success_without_retrying() {
+        let calls = AtomicUsize::new(0);
+
+        let result = retry_with_backoff(Duration::from_secs(1), fast_retry_config(5), || {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async { Ok::<_, anyhow::Error>(42) }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(result, 42);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn retry_with_backoff_retries_until_success() {
+        let calls = AtomicUsize::new(0);
+
+        let result = retry_with_backoff(Duration::from_secs(1), fast_retry_config(5), || {
+            let attempt = calls.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if attempt < 2 {
+                    bail!("not yet")
+                }
+                Ok::<_, anyhow::Error>(())
+            }
+        })
+        .await;
+
+        assert!(result.is_ok());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn retry_with_backoff_gives_up_after_max_attempts() {
+        let calls = AtomicUsize::new(0);
+
+        let result = retry_with_backoff(Duration::from_secs(1), fast_retry_config(3), || {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async { bail!("always fails") }
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn retry_with_backoff_bounds_total_time_by_deadline_not_attempts() {
+        // An operation that never resolves: if the deadline bounded each attempt instead of the
+        // whole retry budget, this would hang for attempts * attempt_timeout instead of
+        // returning shortly after the single overall deadline.
+        let started = std::time::Instant::now();
+        let result = retry_with_backoff(Duration::from_millis(20), fast_retry_config(5), || {
+            std::future::pending::<Result<()>>()
+        })
+        .await;
+
+        assert!(result.is_err());
+        // A per-attempt deadline would take at least 5 * 20ms.
+        assert!(started.elapsed() < Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn cancellation_before_gate_resolves_skips_the_effect() {
+        let cancel_token = CancellationToken::new();
+        let effect_ran = AtomicUsize::new(0);
+
+        cancel_token.cancel();
+
+        let result = run_past_cancellation_point(
+            &cancel_token,
+            std::future::pending::<Result<()>>(),
+            async {
+                effect_ran.fetch_add(1, Ordering::SeqCst);
+                Ok::<_, anyhow::Error>(())
+            },
+        )
+        .await
+        .unwrap();
+
+        assert!(result.is_none());
+        assert_eq!(effect_ran.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn cancellation_during_the_effect_does_not_interrupt_it() {
+        let cancel_token = CancellationToken::new();
+
+        let result = run_past_cancellation_point(&cancel_token, async { Ok(()) }, async {
+            // A cancellation requested once we're already past the gate must not abort this.
+            cancel_token.cancel();
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            Ok::<_, anyhow::Error>("done")
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(result, Some("done"));
+    }
+}